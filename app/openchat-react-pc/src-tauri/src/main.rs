@@ -5,6 +5,9 @@ use tauri::{Manager, SystemTray, SystemTrayEvent, SystemTrayMenu, SystemTrayMenu
 
 mod commands;
 mod pty;
+mod pty_error;
+mod pty_git;
+mod pty_history;
 
 fn main() {
     // 创建系统托盘菜单
@@ -16,6 +19,10 @@ fn main() {
     let system_tray = SystemTray::new().with_menu(tray_menu);
 
     tauri::Builder::default()
+        .setup(|app| {
+            pty_history::init(&app.handle());
+            Ok(())
+        })
         .system_tray(system_tray)
         .on_system_tray_event(|app, event| match event {
             SystemTrayEvent::LeftClick {
@@ -49,6 +56,9 @@ fn main() {
             pty::write_pty,
             pty::resize_pty,
             pty::destroy_pty,
+            pty_history::pty_history_search,
+            pty_history::pty_history_recent,
+            pty_git::pty_git_status,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
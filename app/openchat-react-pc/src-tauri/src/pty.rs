@@ -4,20 +4,137 @@
  * 提供终端仿真功能
  */
 
+use crate::pty_error::PtyError;
+use crate::pty_history;
 use portable_pty::{CommandBuilder, NativePtySystem, PtyPair, PtySize, PtySystem};
-use std::io::{Read, Write};
-use std::sync::Mutex;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::io::Write;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
 use tauri::Manager;
 
 // 全局 PTY 存储
 use once_cell::sync::Lazy;
 
-static PTY_PAIRS: Lazy<Mutex<std::collections::HashMap<String, PtyPair>>> =
+/// 一个正在运行的 PTY 会话，包含底层的 `PtyPair`、子进程句柄、
+/// 读取线程的控制句柄，以及用于切分历史记录的输入缓冲区
+struct PtySession {
+    pair: PtyPair,
+    child: Box<dyn portable_pty::Child + Send + Sync>,
+    cwd: String,
+    input_buffer: String,
+    reader_stop: Arc<AtomicBool>,
+    reader_thread: Option<JoinHandle<()>>,
+}
+
+static PTY_PAIRS: Lazy<Mutex<std::collections::HashMap<String, PtySession>>> =
     Lazy::new(|| Mutex::new(std::collections::HashMap::new()));
 
+/// 在独立线程中读取 PTY 输出，转发给前端，并追加到历史记录中当前正在录制的命令上
+fn spawn_reader_thread(
+    app_handle: tauri::AppHandle,
+    id: String,
+    mut reader: Box<dyn std::io::Read + Send>,
+    stop_flag: Arc<AtomicBool>,
+) -> JoinHandle<()> {
+    thread::spawn(move || {
+        let mut buf = [0u8; 4096];
+        loop {
+            if stop_flag.load(Ordering::Relaxed) {
+                break;
+            }
+
+            match reader.read(&mut buf) {
+                Ok(0) => break,
+                Ok(n) => {
+                    let chunk = String::from_utf8_lossy(&buf[..n]).into_owned();
+                    pty_history::append_output(&id, &chunk);
+                    let _ = app_handle.emit_all(&format!("pty://data/{}", id), chunk);
+                }
+                Err(_) => break,
+            }
+        }
+
+        let exit_code = exit_code_for(&id);
+        pty_history::finish_session(&id, exit_code);
+        let _ = app_handle.emit_all(&format!("pty://exit/{}", id), ());
+    })
+}
+
+/// 读取子进程的退出码（若已退出）
+fn exit_code_for(id: &str) -> Option<i32> {
+    let mut pairs = PTY_PAIRS.lock().unwrap();
+    let session = pairs.get_mut(id)?;
+    session
+        .child
+        .try_wait()
+        .ok()
+        .flatten()
+        .map(|status| status.exit_code() as i32)
+}
+
+/// 创建 PTY 时可选的附加配置：工作目录、环境变量与附加参数
+#[derive(Debug, Default, Deserialize)]
+pub struct PtyConfig {
+    pub cwd: Option<String>,
+    #[serde(default)]
+    pub env: HashMap<String, String>,
+    #[serde(default)]
+    pub args: Vec<String>,
+}
+
+/// 在 Unix 上探测默认 shell：优先使用 `$SHELL`，否则退回 `/bin/bash`
+#[cfg(not(target_os = "windows"))]
+fn default_shell() -> Result<String, PtyError> {
+    if let Ok(shell) = std::env::var("SHELL") {
+        if !shell.is_empty() {
+            return Ok(shell);
+        }
+    }
+    if std::path::Path::new("/bin/bash").exists() {
+        return Ok("/bin/bash".to_string());
+    }
+    Err(PtyError::ShellNotFound(
+        "$SHELL is unset and /bin/bash does not exist".to_string(),
+    ))
+}
+
+/// 在 Windows 上按优先级探测 `pwsh.exe` / `powershell.exe` / `cmd.exe`
+#[cfg(target_os = "windows")]
+fn default_shell() -> Result<String, PtyError> {
+    for candidate in ["pwsh.exe", "powershell.exe", "cmd.exe"] {
+        if is_on_path(candidate) {
+            return Ok(candidate.to_string());
+        }
+    }
+    Err(PtyError::ShellNotFound(
+        "none of pwsh, powershell, cmd were found on PATH".to_string(),
+    ))
+}
+
+#[cfg(target_os = "windows")]
+fn is_on_path(executable: &str) -> bool {
+    std::env::var_os("PATH")
+        .map(|paths| std::env::split_paths(&paths).any(|dir| dir.join(executable).is_file()))
+        .unwrap_or(false)
+}
+
 /// 创建 PTY
 #[tauri::command]
-pub fn create_pty(id: String, shell: Option<String>) -> Result<(), String> {
+pub fn create_pty(
+    app_handle: tauri::AppHandle,
+    id: String,
+    shell: Option<String>,
+    config: Option<PtyConfig>,
+) -> Result<(), PtyError> {
+    let config = config.unwrap_or_default();
+    let shell = match shell {
+        Some(shell) => shell,
+        None => default_shell()?,
+    };
+
     let pty_system = NativePtySystem::default();
 
     let pair = pty_system
@@ -27,14 +144,58 @@ pub fn create_pty(id: String, shell: Option<String>) -> Result<(), String> {
             pixel_width: 0,
             pixel_height: 0,
         })
-        .map_err(|e| e.to_string())?;
+        .map_err(|e| PtyError::Io(e.to_string()))?;
+
+    let mut cmd = CommandBuilder::new(&shell);
+    for arg in &config.args {
+        cmd.arg(arg);
+    }
+    if let Some(cwd) = &config.cwd {
+        cmd.cwd(cwd);
+    }
+    // 默认继承父进程的环境变量，再叠加调用方显式传入的覆盖项
+    cmd.env_clear();
+    for (key, value) in std::env::vars() {
+        cmd.env(key, value);
+    }
+    for (key, value) in &config.env {
+        cmd.env(key, value);
+    }
 
-    let cmd = CommandBuilder::new(shell.as_deref().unwrap_or("bash"));
-    pair.slave
+    let child = pair
+        .slave
         .spawn_command(cmd)
-        .map_err(|e| e.to_string())?;
+        .map_err(|e| PtyError::SpawnFailed(e.to_string()))?;
+
+    let cwd = config.cwd.clone().unwrap_or_else(|| {
+        std::env::current_dir()
+            .map(|p| p.to_string_lossy().into_owned())
+            .unwrap_or_default()
+    });
 
-    PTY_PAIRS.lock().unwrap().insert(id, pair);
+    let reader = pair
+        .master
+        .try_clone_reader()
+        .map_err(|e| PtyError::Io(e.to_string()))?;
+    let reader_stop = Arc::new(AtomicBool::new(false));
+    let reader_thread =
+        spawn_reader_thread(app_handle.clone(), id.clone(), reader, reader_stop.clone());
+
+    PTY_PAIRS.lock().unwrap().insert(
+        id.clone(),
+        PtySession {
+            pair,
+            child,
+            cwd: cwd.clone(),
+            input_buffer: String::new(),
+            reader_stop,
+            reader_thread: Some(reader_thread),
+        },
+    );
+
+    // 会话插入 PTY_PAIRS 之后再启动监听，避免 pty_git_status 在两者之间的窗口期
+    // 查不到 cwd 而误报 "not a repo"
+    crate::pty_git::start_watch(&app_handle, &id, &cwd);
 
     Ok(())
 }
@@ -43,22 +204,42 @@ pub fn create_pty(id: String, shell: Option<String>) -> Result<(), String> {
 #[tauri::command]
 pub fn write_pty(id: String, data: String) -> Result<(), String> {
     let mut pairs = PTY_PAIRS.lock().unwrap();
-    let pair = pairs.get_mut(&id).ok_or("PTY not found")?;
+    let session = pairs.get_mut(&id).ok_or("PTY not found")?;
 
-    let mut writer = pair.master.take_writer().map_err(|e| e.to_string())?;
+    let mut writer = session.pair.master.take_writer().map_err(|e| e.to_string())?;
     writer.write_all(data.as_bytes()).map_err(|e| e.to_string())?;
     writer.flush().map_err(|e| e.to_string())?;
 
+    record_input_for_history(session, &id, &data);
+
     Ok(())
 }
 
+/// 将写入终端的数据按行切分，每当遇到换行就把累积的缓冲区记作一条新命令
+fn record_input_for_history(session: &mut PtySession, id: &str, data: &str) {
+    for ch in data.chars() {
+        match ch {
+            '\n' | '\r' => {
+                let command = session.input_buffer.trim().to_string();
+                session.input_buffer.clear();
+                if !command.is_empty() {
+                    pty_history::record_command(id, &command, &session.cwd);
+                }
+            }
+            _ => session.input_buffer.push(ch),
+        }
+    }
+}
+
 /// 调整 PTY 大小
 #[tauri::command]
 pub fn resize_pty(id: String, cols: u16, rows: u16) -> Result<(), String> {
     let mut pairs = PTY_PAIRS.lock().unwrap();
-    let pair = pairs.get_mut(&id).ok_or("PTY not found")?;
+    let session = pairs.get_mut(&id).ok_or("PTY not found")?;
 
-    pair.master
+    session
+        .pair
+        .master
         .resize(PtySize {
             rows,
             cols,
@@ -73,6 +254,51 @@ pub fn resize_pty(id: String, cols: u16, rows: u16) -> Result<(), String> {
 /// 销毁 PTY
 #[tauri::command]
 pub fn destroy_pty(id: String) -> Result<(), String> {
-    PTY_PAIRS.lock().unwrap().remove(&id);
+    let session = PTY_PAIRS.lock().unwrap().remove(&id);
+
+    if let Some(mut session) = session {
+        session.reader_stop.store(true, Ordering::Relaxed);
+        // 读取线程持有的是克隆出来的读取端，仅丢弃 PtyPair 不足以让它返回；
+        // 先结束子进程，使 PTY 从端关闭，阻塞中的读取随之返回
+        let _ = session.child.kill();
+        drop(session.pair);
+        if let Some(handle) = session.reader_thread.take() {
+            let _ = handle.join();
+        }
+    }
+    crate::pty_git::stop_watch(&id);
     Ok(())
 }
+
+/// 读取某个会话的工作目录，供 `pty_git` 模块在不重复持有 `PTY_PAIRS` 的情况下查询
+pub(crate) fn session_cwd(id: &str) -> Option<String> {
+    PTY_PAIRS.lock().unwrap().get(id).map(|s| s.cwd.clone())
+}
+
+#[cfg(test)]
+#[cfg(not(target_os = "windows"))]
+mod tests {
+    use super::*;
+
+    // `default_shell` reads the process-wide `$SHELL` env var, so these two
+    // tests can't run concurrently with each other without racing.
+    #[test]
+    fn test_default_shell_prefers_shell_env_var() {
+        std::env::set_var("SHELL", "/usr/bin/fish");
+        assert_eq!(default_shell().unwrap(), "/usr/bin/fish");
+        std::env::remove_var("SHELL");
+    }
+
+    #[test]
+    fn test_default_shell_falls_back_when_shell_env_var_unset() {
+        std::env::remove_var("SHELL");
+        let shell = default_shell();
+        // /bin/bash may not exist in every sandbox; just check that with
+        // $SHELL unset we get either /bin/bash or ShellNotFound.
+        if let Ok(shell) = shell {
+            assert_eq!(shell, "/bin/bash");
+        } else {
+            assert!(matches!(shell, Err(PtyError::ShellNotFound(_))));
+        }
+    }
+}
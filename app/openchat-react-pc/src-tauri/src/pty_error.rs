@@ -0,0 +1,31 @@
+/**
+ * PTY 错误类型
+ *
+ * 用结构化的错误枚举代替字符串错误，让前端可以区分
+ * "找不到可用 shell" 与 "启动进程失败" 等不同失败原因
+ */
+
+use serde::Serialize;
+
+#[derive(Debug, Serialize)]
+#[serde(tag = "kind", content = "message")]
+pub enum PtyError {
+    /// 没有找到可用的 shell（既没有显式指定，也没有探测到平台默认值）
+    ShellNotFound(String),
+    /// 已经确定要使用的 shell，但启动子进程失败
+    SpawnFailed(String),
+    /// 打开 PTY 或克隆 PTY 读取端等底层操作失败
+    Io(String),
+}
+
+impl std::fmt::Display for PtyError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PtyError::ShellNotFound(msg) => write!(f, "shell not found: {}", msg),
+            PtyError::SpawnFailed(msg) => write!(f, "failed to spawn shell: {}", msg),
+            PtyError::Io(msg) => write!(f, "pty io error: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for PtyError {}
@@ -0,0 +1,252 @@
+/**
+ * PTY Git 状态模块
+ *
+ * 为终端会话的工作目录提供 git 分支 / ahead-behind / dirty 状态。
+ * 每个会话在创建时会起一个文件系统监听线程，状态变化时主动推送
+ * `pty://git/{id}` 事件，这样 UI 无需在每次按键时都 shell 出 git 命令
+ */
+
+use git2::{BranchType, Repository, StatusOptions};
+use notify::{Config, Event, RecommendedWatcher, RecursiveMode, Watcher};
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{channel, RecvTimeoutError};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+use tauri::Manager;
+
+/// 一次 git 状态查询的结果
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct GitStatus {
+    pub is_repo: bool,
+    pub branch: Option<String>,
+    pub ahead: usize,
+    pub behind: usize,
+    pub dirty: bool,
+}
+
+impl GitStatus {
+    fn not_a_repo() -> Self {
+        GitStatus {
+            is_repo: false,
+            branch: None,
+            ahead: 0,
+            behind: 0,
+            dirty: false,
+        }
+    }
+}
+
+/// 从 `cwd` 向上查找 `.git` 并计算当前状态；找不到仓库时返回 `is_repo: false`
+fn compute_status(cwd: &str) -> GitStatus {
+    let repo = match Repository::discover(cwd) {
+        Ok(repo) => repo,
+        Err(_) => return GitStatus::not_a_repo(),
+    };
+
+    let branch = repo
+        .head()
+        .ok()
+        .and_then(|head| head.shorthand().map(|s| s.to_string()));
+
+    let mut ahead = 0;
+    let mut behind = 0;
+    if let Some(branch_name) = &branch {
+        if let Ok(local_branch) = repo.find_branch(branch_name, BranchType::Local) {
+            let local_oid = local_branch.get().target();
+            let upstream_oid = local_branch
+                .upstream()
+                .ok()
+                .and_then(|upstream| upstream.get().target());
+
+            if let (Some(local_oid), Some(upstream_oid)) = (local_oid, upstream_oid) {
+                if let Ok((a, b)) = repo.graph_ahead_behind(local_oid, upstream_oid) {
+                    ahead = a;
+                    behind = b;
+                }
+            }
+        }
+    }
+
+    let mut opts = StatusOptions::new();
+    opts.include_untracked(true);
+    let dirty = repo
+        .statuses(Some(&mut opts))
+        .map(|statuses| !statuses.is_empty())
+        .unwrap_or(false);
+
+    GitStatus {
+        is_repo: true,
+        branch,
+        ahead,
+        behind,
+        dirty,
+    }
+}
+
+struct WatchHandle {
+    stop: Arc<AtomicBool>,
+    thread: JoinHandle<()>,
+}
+
+static WATCHERS: Lazy<Mutex<HashMap<String, WatchHandle>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// 为会话 `id` 启动一个监听 `cwd` 的后台线程，工作区变化时重新计算状态并在变化时广播事件
+pub fn start_watch(app_handle: &tauri::AppHandle, id: &str, cwd: &str) {
+    let stop = Arc::new(AtomicBool::new(false));
+    let thread = spawn_watch_thread(app_handle.clone(), id.to_string(), cwd.to_string(), stop.clone());
+    WATCHERS
+        .lock()
+        .unwrap()
+        .insert(id.to_string(), WatchHandle { stop, thread });
+}
+
+/// 停止并回收会话 `id` 对应的监听线程
+pub fn stop_watch(id: &str) {
+    // 先取出 handle 再释放锁，避免 join（空闲时最多等待一个轮询周期，正在防抖排空
+    // 事件或重新计算状态时会更久）时持有全局锁
+    // 阻塞其他会话的 start_watch/stop_watch
+    let handle = WATCHERS.lock().unwrap().remove(id);
+    if let Some(handle) = handle {
+        handle.stop.store(true, Ordering::Relaxed);
+        let _ = handle.thread.join();
+    }
+}
+
+fn spawn_watch_thread(
+    app_handle: tauri::AppHandle,
+    id: String,
+    cwd: String,
+    stop: Arc<AtomicBool>,
+) -> JoinHandle<()> {
+    thread::spawn(move || {
+        let (tx, rx) = channel::<notify::Result<Event>>();
+        let mut watcher = match RecommendedWatcher::new(tx, Config::default()) {
+            Ok(watcher) => watcher,
+            Err(_) => return,
+        };
+        // 找不到目录（会话的 cwd 已被删除等）时直接放弃监听，状态仍可通过 pty_git_status 按需查询
+        if watcher.watch(Path::new(&cwd), RecursiveMode::Recursive).is_err() {
+            return;
+        }
+
+        let mut last = compute_status(&cwd);
+
+        loop {
+            if stop.load(Ordering::Relaxed) {
+                break;
+            }
+
+            match rx.recv_timeout(Duration::from_millis(500)) {
+                Ok(_) => {
+                    // 防抖：像 `npm install`/`cargo build` 这类操作会在短时间内产生大量文件
+                    // 事件，先把这段时间内的事件排空，再统一重新计算一次状态
+                    while rx.recv_timeout(Duration::from_millis(200)).is_ok() {}
+
+                    let current = compute_status(&cwd);
+                    if current != last {
+                        last = current.clone();
+                        let _ = app_handle.emit_all(&format!("pty://git/{}", id), current);
+                    }
+                }
+                Err(RecvTimeoutError::Timeout) => continue,
+                Err(RecvTimeoutError::Disconnected) => break,
+            }
+        }
+    })
+}
+
+/// 查询某个终端会话工作目录的 git 状态
+#[tauri::command]
+pub fn pty_git_status(id: String) -> GitStatus {
+    match crate::pty::session_cwd(&id) {
+        Some(cwd) => compute_status(&cwd),
+        None => GitStatus::not_a_repo(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::sync::atomic::AtomicUsize;
+
+    static SCRATCH_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+    /// 在系统临时目录下创建一个专属的、空的目录，供单个测试使用
+    fn scratch_dir(name: &str) -> std::path::PathBuf {
+        let n = SCRATCH_COUNTER.fetch_add(1, Ordering::Relaxed);
+        let dir = std::env::temp_dir().join(format!("pty_git_test_{}_{}_{}", std::process::id(), n, name));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn commit_all(repo: &Repository, message: &str) {
+        let sig = git2::Signature::now("Test", "test@example.com").unwrap();
+        let mut index = repo.index().unwrap();
+        index
+            .add_all(["*"].iter(), git2::IndexAddOption::DEFAULT, None)
+            .unwrap();
+        index.write().unwrap();
+        let tree_oid = index.write_tree().unwrap();
+        let tree = repo.find_tree(tree_oid).unwrap();
+
+        let parents: Vec<git2::Commit> = repo
+            .head()
+            .ok()
+            .and_then(|head| head.peel_to_commit().ok())
+            .into_iter()
+            .collect();
+        let parent_refs: Vec<&git2::Commit> = parents.iter().collect();
+
+        repo.commit(Some("HEAD"), &sig, &sig, message, &tree, &parent_refs)
+            .unwrap();
+    }
+
+    #[test]
+    fn test_compute_status_outside_any_repo_is_not_a_repo() {
+        let dir = scratch_dir("not_a_repo");
+        let status = compute_status(dir.to_str().unwrap());
+        assert_eq!(status, GitStatus::not_a_repo());
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_compute_status_reports_branch_and_clean_after_commit() {
+        let dir = scratch_dir("clean_repo");
+        let repo = Repository::init(&dir).unwrap();
+        fs::write(dir.join("README.md"), "hello").unwrap();
+        commit_all(&repo, "initial commit");
+
+        let status = compute_status(dir.to_str().unwrap());
+
+        assert!(status.is_repo);
+        assert!(status.branch.is_some());
+        assert!(!status.dirty);
+        assert_eq!(status.ahead, 0);
+        assert_eq!(status.behind, 0);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_compute_status_reports_dirty_with_untracked_file() {
+        let dir = scratch_dir("dirty_repo");
+        let repo = Repository::init(&dir).unwrap();
+        fs::write(dir.join("README.md"), "hello").unwrap();
+        commit_all(&repo, "initial commit");
+
+        fs::write(dir.join("untracked.txt"), "scratch").unwrap();
+
+        let status = compute_status(dir.to_str().unwrap());
+        assert!(status.dirty);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,288 @@
+/**
+ * PTY 历史记录模块
+ *
+ * 记录每个终端会话的输入命令与对应输出，持久化到磁盘，
+ * 支持按子串检索以及按时间倒序取最近若干条，让终端具备反向搜索与会话恢复能力
+ */
+
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// 内存环形缓冲区保留的最近条目数；磁盘文件写入的是同一份快照，
+/// 超出容量被淘汰的条目不再保留在磁盘上
+const RECENT_CAPACITY: usize = 1000;
+
+/// 单条记录的输出最多保留的字节数，超出部分被丢弃，避免一次 `cat`/`npm install`
+/// 把整条历史记录撑到无限大，拖慢持久化写入
+const MAX_OUTPUT_BYTES: usize = 64 * 1024;
+
+/// 一条终端历史记录：一次命令的输入、输出与执行结果
+///
+/// `exit_code` 只有在该命令仍是所在会话最后一条记录、且会话退出时才会被填入
+/// （此时填入的其实是整个 shell 进程的退出码，而非这条命令自身的退出码）。
+/// 这个后端目前没有接入逐条命令的退出状态追踪（例如 shell 侧的提示符标记），
+/// 所以其余所有已完成记录的 `exit_code` 始终为 `None`，UI 不应把它当作
+/// 逐命令的成功/失败指示来渲染。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistoryEntry {
+    pub session_id: String,
+    pub command: String,
+    pub cwd: String,
+    pub started_at: u64,
+    pub exit_code: Option<i32>,
+    pub output: String,
+}
+
+struct HistoryStore {
+    /// 已完成命令的环形缓冲区，最旧的在前
+    recent: VecDeque<HistoryEntry>,
+    /// 每个会话当前正在录制、尚未终结的命令
+    active: HashMap<String, HistoryEntry>,
+    /// 持久化存储的文件路径，未初始化时为 None（仅内存，不落盘）
+    path: Option<PathBuf>,
+}
+
+impl HistoryStore {
+    fn new() -> Self {
+        Self {
+            recent: VecDeque::new(),
+            active: HashMap::new(),
+            path: None,
+        }
+    }
+
+    fn load(&mut self, path: PathBuf) {
+        if let Ok(data) = fs::read_to_string(&path) {
+            if let Ok(entries) = serde_json::from_str::<Vec<HistoryEntry>>(&data) {
+                self.recent = entries.into();
+            }
+        }
+        self.path = Some(path);
+    }
+
+    /// 结束某个会话当前正在录制的命令，推入环形缓冲区并按容量淘汰最旧的条目。
+    /// 返回落盘所需的路径与当前快照，留给调用方在释放锁之后再写入磁盘，
+    /// 避免持有 `HISTORY` 锁的同时做同步 IO。
+    fn finalize(&mut self, session_id: &str, exit_code: Option<i32>) -> Option<(PathBuf, Vec<HistoryEntry>)> {
+        let mut entry = self.active.remove(session_id)?;
+        entry.exit_code = exit_code;
+        self.recent.push_back(entry);
+        while self.recent.len() > RECENT_CAPACITY {
+            self.recent.pop_front();
+        }
+        self.path
+            .clone()
+            .map(|path| (path, self.recent.iter().cloned().collect()))
+    }
+}
+
+static HISTORY: Lazy<Mutex<HistoryStore>> = Lazy::new(|| Mutex::new(HistoryStore::new()));
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// 把一份历史快照写入磁盘；在独立线程中运行，不持有 `HISTORY` 锁
+fn spawn_persist(snapshot: Option<(PathBuf, Vec<HistoryEntry>)>) {
+    let Some((path, entries)) = snapshot else {
+        return;
+    };
+    std::thread::spawn(move || {
+        if let Some(parent) = path.parent() {
+            let _ = fs::create_dir_all(parent);
+        }
+        if let Ok(json) = serde_json::to_string(&entries) {
+            let _ = fs::write(&path, json);
+        }
+    });
+}
+
+/// 使用应用数据目录下的 `pty_history.json` 作为持久化存储并加载已有历史
+pub fn init(app_handle: &tauri::AppHandle) {
+    if let Some(dir) = app_handle.path_resolver().app_data_dir() {
+        HISTORY.lock().unwrap().load(dir.join("pty_history.json"));
+    }
+}
+
+/// 记录一条新输入的命令，并结束该会话上一条尚未终结的记录（若存在）
+pub fn record_command(session_id: &str, command: &str, cwd: &str) {
+    let snapshot = {
+        let mut store = HISTORY.lock().unwrap();
+        let snapshot = store.finalize(session_id, None);
+        store.active.insert(
+            session_id.to_string(),
+            HistoryEntry {
+                session_id: session_id.to_string(),
+                command: command.to_string(),
+                cwd: cwd.to_string(),
+                started_at: now_secs(),
+                exit_code: None,
+                output: String::new(),
+            },
+        );
+        snapshot
+    };
+    spawn_persist(snapshot);
+}
+
+/// 将一段 PTY 输出追加到该会话当前正在录制的命令上，超过 `MAX_OUTPUT_BYTES` 的部分被丢弃
+pub fn append_output(session_id: &str, chunk: &str) {
+    let mut store = HISTORY.lock().unwrap();
+    if let Some(entry) = store.active.get_mut(session_id) {
+        if entry.output.len() >= MAX_OUTPUT_BYTES {
+            return;
+        }
+        let remaining = MAX_OUTPUT_BYTES - entry.output.len();
+        if chunk.len() <= remaining {
+            entry.output.push_str(chunk);
+        } else {
+            let mut end = remaining;
+            while end > 0 && !chunk.is_char_boundary(end) {
+                end -= 1;
+            }
+            entry.output.push_str(&chunk[..end]);
+        }
+    }
+}
+
+/// 结束某个会话当前正在录制的命令（进程退出或会话被销毁时调用）
+pub fn finish_session(session_id: &str, exit_code: Option<i32>) {
+    let snapshot = HISTORY.lock().unwrap().finalize(session_id, exit_code);
+    spawn_persist(snapshot);
+}
+
+/// 按命令子串检索历史，按最近优先排序
+fn search_recent(recent: &VecDeque<HistoryEntry>, query: &str) -> Vec<HistoryEntry> {
+    recent
+        .iter()
+        .rev()
+        .filter(|entry| entry.command.contains(query))
+        .cloned()
+        .collect()
+}
+
+/// 返回最近的 `limit` 条历史记录，最近的排在最前
+fn take_recent(recent: &VecDeque<HistoryEntry>, limit: usize) -> Vec<HistoryEntry> {
+    recent.iter().rev().take(limit).cloned().collect()
+}
+
+/// 按命令子串检索历史，按最近优先排序
+#[tauri::command]
+pub fn pty_history_search(query: String) -> Vec<HistoryEntry> {
+    let store = HISTORY.lock().unwrap();
+    search_recent(&store.recent, &query)
+}
+
+/// 返回最近的 `limit` 条历史记录，最近的排在最前
+#[tauri::command]
+pub fn pty_history_recent(limit: usize) -> Vec<HistoryEntry> {
+    let store = HISTORY.lock().unwrap();
+    take_recent(&store.recent, limit)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(session_id: &str, command: &str, started_at: u64) -> HistoryEntry {
+        HistoryEntry {
+            session_id: session_id.to_string(),
+            command: command.to_string(),
+            cwd: "/tmp".to_string(),
+            started_at,
+            exit_code: None,
+            output: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_finalize_moves_active_entry_into_recent() {
+        let mut store = HistoryStore::new();
+        store.active.insert("s1".to_string(), entry("s1", "ls", 1));
+
+        let snapshot = store.finalize("s1", Some(0));
+
+        assert!(store.active.get("s1").is_none());
+        assert_eq!(store.recent.len(), 1);
+        assert_eq!(store.recent[0].exit_code, Some(0));
+        // No path configured, so there's nothing to persist.
+        assert!(snapshot.is_none());
+    }
+
+    #[test]
+    fn test_finalize_with_no_active_entry_is_a_noop() {
+        let mut store = HistoryStore::new();
+        assert!(store.finalize("missing", None).is_none());
+        assert!(store.recent.is_empty());
+    }
+
+    #[test]
+    fn test_recent_evicts_oldest_beyond_capacity() {
+        let mut store = HistoryStore::new();
+        for i in 0..RECENT_CAPACITY + 5 {
+            store
+                .active
+                .insert("s1".to_string(), entry("s1", &format!("cmd-{i}"), i as u64));
+            store.finalize("s1", None);
+        }
+
+        assert_eq!(store.recent.len(), RECENT_CAPACITY);
+        assert_eq!(store.recent.front().unwrap().command, "cmd-5");
+        assert_eq!(store.recent.back().unwrap().command, format!("cmd-{}", RECENT_CAPACITY + 4));
+    }
+
+    #[test]
+    fn test_append_output_caps_at_max_output_bytes() {
+        // append_output works on the global store, so use a session id no
+        // other test touches.
+        let session_id = "test-append-output-cap";
+        HISTORY
+            .lock()
+            .unwrap()
+            .active
+            .insert(session_id.to_string(), entry(session_id, "yes", 0));
+
+        let chunk = "x".repeat(MAX_OUTPUT_BYTES + 100);
+        append_output(session_id, &chunk);
+
+        let mut store = HISTORY.lock().unwrap();
+        assert_eq!(store.active.get(session_id).unwrap().output.len(), MAX_OUTPUT_BYTES);
+        store.active.remove(session_id);
+    }
+
+    #[test]
+    fn test_search_recent_filters_by_substring_and_orders_by_recency() {
+        let mut recent = VecDeque::new();
+        recent.push_back(entry("s1", "git status", 1));
+        recent.push_back(entry("s1", "ls -la", 2));
+        recent.push_back(entry("s1", "git log", 3));
+
+        let hits = search_recent(&recent, "git");
+
+        assert_eq!(hits.len(), 2);
+        assert_eq!(hits[0].command, "git log");
+        assert_eq!(hits[1].command, "git status");
+    }
+
+    #[test]
+    fn test_take_recent_orders_newest_first_and_respects_limit() {
+        let mut recent = VecDeque::new();
+        recent.push_back(entry("s1", "one", 1));
+        recent.push_back(entry("s1", "two", 2));
+        recent.push_back(entry("s1", "three", 3));
+
+        let hits = take_recent(&recent, 2);
+
+        assert_eq!(hits.len(), 2);
+        assert_eq!(hits[0].command, "three");
+        assert_eq!(hits[1].command, "two");
+    }
+}
@@ -0,0 +1,178 @@
+/**
+ * Syntax highlighting module
+ *
+ * Tokenizes fenced code blocks per language and emits escaped HTML with
+ * `tok-*` classes. Doesn't depend on an external grammar database, so it
+ * stays cheap to ship in a size-constrained WASM bundle.
+ */
+
+use std::collections::HashSet;
+
+/// Tokenizes `source` and returns HTML with highlight classes. Returns `None`
+/// for an unknown language so the caller can fall back to plain text.
+pub fn highlight(source: &str, lang: &str) -> Option<String> {
+    let keywords = keywords_for(lang)?;
+    Some(tokenize(source, &keywords))
+}
+
+fn keywords_for(lang: &str) -> Option<HashSet<&'static str>> {
+    let list: &[&str] = match lang.trim().to_ascii_lowercase().as_str() {
+        "rust" | "rs" => &[
+            "fn", "let", "mut", "pub", "struct", "enum", "impl", "trait", "use", "mod", "if",
+            "else", "match", "for", "while", "loop", "return", "break", "continue", "as",
+            "const", "static", "async", "await", "move", "ref", "self", "Self", "where", "dyn",
+            "unsafe", "in", "true", "false",
+        ],
+        "javascript" | "js" | "jsx" => &[
+            "function", "const", "let", "var", "if", "else", "for", "while", "return", "class",
+            "extends", "new", "this", "import", "export", "from", "default", "async", "await",
+            "try", "catch", "finally", "throw", "typeof", "instanceof", "true", "false", "null",
+            "undefined",
+        ],
+        "typescript" | "ts" | "tsx" => &[
+            "function", "const", "let", "var", "if", "else", "for", "while", "return", "class",
+            "extends", "new", "this", "import", "export", "from", "default", "async", "await",
+            "try", "catch", "finally", "throw", "typeof", "instanceof", "interface", "type",
+            "enum", "implements", "public", "private", "protected", "readonly", "true", "false",
+            "null", "undefined",
+        ],
+        "python" | "py" => &[
+            "def", "class", "if", "elif", "else", "for", "while", "return", "import", "from",
+            "as", "try", "except", "finally", "raise", "with", "lambda", "yield", "pass",
+            "break", "continue", "True", "False", "None", "and", "or", "not", "in", "is",
+        ],
+        "go" => &[
+            "func", "package", "import", "var", "const", "type", "struct", "interface", "if",
+            "else", "for", "range", "return", "go", "chan", "select", "switch", "case",
+            "default", "defer", "map", "true", "false", "nil",
+        ],
+        "java" | "c" | "cpp" | "c++" => &[
+            "if", "else", "for", "while", "return", "class", "struct", "public", "private",
+            "protected", "static", "void", "new", "this", "true", "false", "null", "const",
+            "int", "char", "float", "double", "long", "short", "namespace", "template",
+            "typename", "include",
+        ],
+        "bash" | "sh" | "shell" => &[
+            "if", "then", "else", "elif", "fi", "for", "do", "done", "while", "function",
+            "return", "export", "local", "case", "esac", "in",
+        ],
+        "json" => &[],
+        _ => return None,
+    };
+    Some(list.iter().copied().collect())
+}
+
+/// A simple character-level tokenizer: recognizes strings, comments, numbers,
+/// keywords and identifiers directly followed by `(` (as function names),
+/// escaping everything else as-is.
+fn tokenize(source: &str, keywords: &HashSet<&str>) -> String {
+    let chars: Vec<char> = source.chars().collect();
+    let mut out = String::with_capacity(source.len() + source.len() / 4);
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+
+        if c == '"' || c == '\'' {
+            let start = i;
+            i += 1;
+            while i < chars.len() {
+                if chars[i] == '\\' && i + 1 < chars.len() {
+                    i += 2;
+                    continue;
+                }
+                if chars[i] == c {
+                    i += 1;
+                    break;
+                }
+                i += 1;
+            }
+            push_token(&mut out, "tok-string", &chars[start..i]);
+            continue;
+        }
+
+        if c == '/' && chars.get(i + 1) == Some(&'/') {
+            let start = i;
+            while i < chars.len() && chars[i] != '\n' {
+                i += 1;
+            }
+            push_token(&mut out, "tok-comment", &chars[start..i]);
+            continue;
+        }
+
+        if c == '#' {
+            let start = i;
+            while i < chars.len() && chars[i] != '\n' {
+                i += 1;
+            }
+            push_token(&mut out, "tok-comment", &chars[start..i]);
+            continue;
+        }
+
+        if c.is_ascii_digit() {
+            let start = i;
+            while i < chars.len() && (chars[i].is_ascii_alphanumeric() || chars[i] == '.') {
+                i += 1;
+            }
+            push_token(&mut out, "tok-number", &chars[start..i]);
+            continue;
+        }
+
+        if c.is_alphabetic() || c == '_' {
+            let start = i;
+            while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+                i += 1;
+            }
+            let word: String = chars[start..i].iter().collect();
+            if keywords.contains(word.as_str()) {
+                push_span(&mut out, "tok-keyword", &word);
+            } else if chars.get(i) == Some(&'(') {
+                push_span(&mut out, "tok-function", &word);
+            } else {
+                out.push_str(&escape_html(&word));
+            }
+            continue;
+        }
+
+        out.push_str(&escape_html(&c.to_string()));
+        i += 1;
+    }
+
+    out
+}
+
+fn push_token(out: &mut String, class: &str, chars: &[char]) {
+    let text: String = chars.iter().collect();
+    push_span(out, class, &text);
+}
+
+fn push_span(out: &mut String, class: &str, text: &str) {
+    out.push_str("<span class=\"");
+    out.push_str(class);
+    out.push_str("\">");
+    out.push_str(&escape_html(text));
+    out.push_str("</span>");
+}
+
+/// Escapes HTML special characters so untrusted source snippets can't break
+/// the surrounding markup.
+pub fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Escapes text for use in an HTML attribute value (currently identical to
+/// `escape_html`).
+pub fn escape_attr(text: &str) -> String {
+    escape_html(text)
+}
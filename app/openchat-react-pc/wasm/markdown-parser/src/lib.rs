@@ -1,7 +1,9 @@
+mod highlight;
+mod semantic_search;
 mod utils;
 
 use wasm_bindgen::prelude::*;
-use pulldown_cmark::{Parser, Options, html};
+use pulldown_cmark::{CodeBlockKind, Event, Options, Parser, Tag, html};
 
 // When the `wee_alloc` feature is enabled, use `wee_alloc` as the global
 // allocator.
@@ -119,6 +121,102 @@ pub fn parse_markdown_with_options(
     }
 }
 
+/// Parse markdown to HTML with syntax-highlighted fenced code blocks
+///
+/// # Arguments
+/// * `markdown` - The markdown string to parse
+/// * `enable_syntax_highlighting` - Whether to tokenize fenced code blocks into `tok-*` spans
+/// * `highlight_theme` - Optional theme name, forwarded as a `data-theme` attribute on the
+///   `<pre>` of each fenced code block; ignored when highlighting is disabled
+///
+/// # Returns
+/// * `ParseResult` - The parsing result containing HTML or error
+#[wasm_bindgen]
+pub fn parse_markdown_highlighted(
+    markdown: &str,
+    enable_syntax_highlighting: bool,
+    highlight_theme: Option<String>,
+) -> ParseResult {
+    let mut options = Options::empty();
+    options.insert(Options::ENABLE_STRIKETHROUGH);
+    options.insert(Options::ENABLE_TABLES);
+    options.insert(Options::ENABLE_TASKLISTS);
+    options.insert(Options::ENABLE_SMART_PUNCTUATION);
+
+    let parser = Parser::new_ext(markdown, options);
+    let mut html_output = String::new();
+
+    let result = if enable_syntax_highlighting {
+        let events = highlight_code_blocks(parser, highlight_theme.as_deref());
+        html::push_html(&mut html_output, events.into_iter())
+    } else {
+        html::push_html(&mut html_output, parser)
+    };
+
+    match result {
+        Ok(_) => ParseResult {
+            html: html_output,
+            success: true,
+            error: String::new(),
+        },
+        Err(e) => ParseResult {
+            html: String::new(),
+            success: false,
+            error: format!("Parse error: {:?}", e),
+        },
+    }
+}
+
+/// Intercept fenced code-block events, replacing the default `<pre><code>` output with
+/// highlighted, class-annotated HTML built from the fence's language info string
+fn highlight_code_blocks<'a>(parser: Parser<'a, 'a>, theme: Option<&str>) -> Vec<Event<'a>> {
+    let mut events = Vec::new();
+    let mut current_lang: Option<String> = None;
+    let mut code_buffer = String::new();
+
+    for event in parser {
+        match event {
+            Event::Start(Tag::CodeBlock(CodeBlockKind::Fenced(lang))) => {
+                current_lang = Some(lang.to_string());
+                code_buffer.clear();
+            }
+            Event::Text(text) if current_lang.is_some() => {
+                code_buffer.push_str(&text);
+            }
+            Event::End(Tag::CodeBlock(CodeBlockKind::Fenced(_))) if current_lang.is_some() => {
+                let lang = current_lang.take().unwrap_or_default();
+                let block_html = render_code_block(&code_buffer, &lang, theme);
+                events.push(Event::Html(block_html.into()));
+                code_buffer.clear();
+            }
+            other => events.push(other),
+        }
+    }
+
+    events
+}
+
+/// Render one fenced code block to HTML, falling back to plain escaped text for unknown languages
+fn render_code_block(source: &str, lang: &str, theme: Option<&str>) -> String {
+    let theme_attr = theme
+        .map(|t| format!(" data-theme=\"{}\"", highlight::escape_attr(t)))
+        .unwrap_or_default();
+
+    match highlight::highlight(source, lang) {
+        Some(body) => format!(
+            "<pre{theme_attr}><code class=\"language-{lang}\">{body}</code></pre>\n",
+            theme_attr = theme_attr,
+            lang = highlight::escape_attr(lang),
+            body = body
+        ),
+        None => format!(
+            "<pre{theme_attr}><code>{body}</code></pre>\n",
+            theme_attr = theme_attr,
+            body = highlight::escape_html(source)
+        ),
+    }
+}
+
 /// Batch parse multiple markdown strings
 /// 
 /// # Arguments
@@ -224,4 +322,22 @@ mod tests {
         let time = get_reading_time(&text, Some(200));
         assert!((time - 1.0).abs() < 0.01);
     }
+
+    #[test]
+    fn test_parse_markdown_highlighted() {
+        let md = "```rust\nfn main() {}\n```";
+        let result = parse_markdown_highlighted(md, true, None);
+        assert!(result.success);
+        assert!(result.html.contains("tok-keyword"));
+        assert!(result.html.contains("language-rust"));
+    }
+
+    #[test]
+    fn test_parse_markdown_highlighted_unknown_lang_falls_back_to_plain() {
+        let md = "```brainfuck\n<script>\n```";
+        let result = parse_markdown_highlighted(md, true, None);
+        assert!(result.success);
+        assert!(!result.html.contains("tok-"));
+        assert!(result.html.contains("&lt;script&gt;"));
+    }
 }
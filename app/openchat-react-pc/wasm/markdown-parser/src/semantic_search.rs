@@ -0,0 +1,338 @@
+/**
+ * Semantic search module
+ *
+ * Splits chat messages into semantic chunks along heading/paragraph
+ * boundaries and indexes them against embedding vectors supplied by the
+ * frontend (the embedding model itself runs on the JS side; Rust only
+ * handles chunking, storage and similarity search), then answers top-k
+ * nearest-neighbor queries by cosine similarity.
+ */
+
+use pulldown_cmark::{Event, Parser, Tag};
+use serde::{Deserialize, Serialize};
+use std::cell::RefCell;
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+use wasm_bindgen::prelude::*;
+
+/// Target token-count range for each chunk, and the number of blocks kept
+/// overlapping between adjacent chunks.
+const MIN_CHUNK_TOKENS: usize = 200;
+const MAX_CHUNK_TOKENS: usize = 400;
+const OVERLAP_BLOCKS: usize = 1;
+
+/// A semantic chunk carved out of a message.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Chunk {
+    pub message_id: String,
+    pub start_byte: usize,
+    pub end_byte: usize,
+    pub text: String,
+}
+
+fn approx_token_count(text: &str) -> usize {
+    text.split_whitespace().count()
+}
+
+/// Splits markdown along heading/paragraph boundaries, merging adjacent
+/// blocks until a chunk reaches MIN_CHUNK_TOKENS. Blocks are never split, so
+/// a chunk can exceed MAX_CHUNK_TOKENS, and the last chunk (or a message with
+/// little text) can stay below MIN_CHUNK_TOKENS. A chunk made of several
+/// blocks shares its last `OVERLAP_BLOCKS` blocks with the next chunk so
+/// meaning isn't cut off at a boundary; a single-block chunk has no overlap.
+fn split_into_chunks(message_id: &str, markdown: &str) -> Vec<Chunk> {
+    let mut blocks: Vec<(usize, usize)> = Vec::new();
+    let mut block_start: Option<usize> = None;
+
+    for (event, range) in Parser::new(markdown).into_offset_iter() {
+        match event {
+            Event::Start(Tag::Heading(..)) | Event::Start(Tag::Paragraph) => {
+                block_start = Some(range.start);
+            }
+            Event::End(Tag::Heading(..)) | Event::End(Tag::Paragraph) => {
+                if let Some(start) = block_start.take() {
+                    blocks.push((start, range.end));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let mut chunks = Vec::new();
+    let mut i = 0;
+    while i < blocks.len() {
+        let chunk_start = blocks[i].0;
+        let mut chunk_end = blocks[i].1;
+        let mut j = i + 1;
+
+        while j < blocks.len() && approx_token_count(&markdown[chunk_start..chunk_end]) < MIN_CHUNK_TOKENS {
+            chunk_end = blocks[j].1;
+            j += 1;
+            if approx_token_count(&markdown[chunk_start..chunk_end]) >= MAX_CHUNK_TOKENS {
+                break;
+            }
+        }
+
+        chunks.push(Chunk {
+            message_id: message_id.to_string(),
+            start_byte: chunk_start,
+            end_byte: chunk_end,
+            text: markdown[chunk_start..chunk_end].to_string(),
+        });
+
+        if j >= blocks.len() {
+            break;
+        }
+        // The next chunk starts OVERLAP_BLOCKS blocks back from this chunk's end, keeping a small overlap
+        i = j.saturating_sub(OVERLAP_BLOCKS).max(i + 1);
+    }
+
+    chunks
+}
+
+/// Splits a message into semantic chunks, for the JS side to compute an
+/// embedding per chunk and call `index_chunk`.
+#[wasm_bindgen]
+pub fn chunk_message(message_id: String, markdown: String) -> Result<JsValue, JsValue> {
+    let chunks = split_into_chunks(&message_id, &markdown);
+    serde_wasm_bindgen::to_value(&chunks)
+        .map_err(|e| JsValue::from_str(&format!("Serialization error: {:?}", e)))
+}
+
+/// A semantic chunk stored in the index: its content plus the embedding
+/// vector, normalized at write time.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct IndexedChunk {
+    message_id: String,
+    start_byte: usize,
+    end_byte: usize,
+    text: String,
+    vector: Vec<f32>,
+    seq: usize,
+}
+
+thread_local! {
+    static INDEX: RefCell<Vec<IndexedChunk>> = RefCell::new(Vec::new());
+    static NEXT_SEQ: RefCell<usize> = RefCell::new(0);
+}
+
+fn normalize(vector: &[f32]) -> Vec<f32> {
+    let norm = vector.iter().map(|v| v * v).sum::<f32>().sqrt();
+    if norm == 0.0 {
+        return vector.to_vec();
+    }
+    vector.iter().map(|v| v / norm).collect()
+}
+
+fn dot(a: &[f32], b: &[f32]) -> f32 {
+    a.iter().zip(b.iter()).map(|(x, y)| x * y).sum()
+}
+
+/// Writes a semantic chunk and its embedding into the index; the vector is
+/// normalized once at write time, so search can compute cosine similarity as
+/// a plain dot product without renormalizing on every query.
+#[wasm_bindgen]
+pub fn index_chunk(
+    message_id: String,
+    start_byte: usize,
+    end_byte: usize,
+    text: String,
+    embedding: Vec<f32>,
+) {
+    let vector = normalize(&embedding);
+    let seq = NEXT_SEQ.with(|s| {
+        let mut s = s.borrow_mut();
+        let current = *s;
+        *s += 1;
+        current
+    });
+
+    INDEX.with(|index| {
+        index.borrow_mut().push(IndexedChunk {
+            message_id,
+            start_byte,
+            end_byte,
+            text,
+            vector,
+            seq,
+        });
+    });
+}
+
+/// A single search result.
+#[derive(Debug, Clone, Serialize)]
+pub struct SearchHit {
+    pub message_id: String,
+    pub start_byte: usize,
+    pub end_byte: usize,
+    pub text: String,
+    pub score: f32,
+}
+
+/// An entry in the heap: ordered by score ascending, with ties broken in
+/// favor of the earlier-written (smaller `seq`) entry, so the heap's top is
+/// always the entry that should be evicted next — on a tie, the later-
+/// written chunk is evicted, keeping earlier-inserted chunks stable.
+struct HeapEntry {
+    score: f32,
+    seq: usize,
+    hit: SearchHit,
+}
+
+impl PartialEq for HeapEntry {
+    fn eq(&self, other: &Self) -> bool {
+        self.score == other.score && self.seq == other.seq
+    }
+}
+impl Eq for HeapEntry {}
+impl PartialOrd for HeapEntry {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+impl Ord for HeapEntry {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other
+            .score
+            .partial_cmp(&self.score)
+            .unwrap_or(Ordering::Equal)
+            .then_with(|| self.seq.cmp(&other.seq))
+    }
+}
+
+/// Scores every chunk in the index by cosine similarity, keeping the
+/// highest-scoring entries in a min-heap of capacity `top_k`. Chunks whose
+/// dimension doesn't match the query vector are skipped. Results are
+/// returned sorted by score descending, then by insertion order ascending.
+#[wasm_bindgen]
+pub fn semantic_search(query_embedding: Vec<f32>, top_k: usize) -> Result<JsValue, JsValue> {
+    let query = normalize(&query_embedding);
+    let dim = query.len();
+    let mut heap: BinaryHeap<HeapEntry> = BinaryHeap::with_capacity(top_k + 1);
+
+    INDEX.with(|index| {
+        for chunk in index.borrow().iter() {
+            if chunk.vector.len() != dim {
+                continue;
+            }
+
+            let score = dot(&query, &chunk.vector);
+            heap.push(HeapEntry {
+                score,
+                seq: chunk.seq,
+                hit: SearchHit {
+                    message_id: chunk.message_id.clone(),
+                    start_byte: chunk.start_byte,
+                    end_byte: chunk.end_byte,
+                    text: chunk.text.clone(),
+                    score,
+                },
+            });
+
+            if heap.len() > top_k {
+                heap.pop();
+            }
+        }
+    });
+
+    let mut ranked: Vec<HeapEntry> = heap.into_vec();
+    ranked.sort_by(|a, b| {
+        b.score
+            .partial_cmp(&a.score)
+            .unwrap_or(Ordering::Equal)
+            .then_with(|| a.seq.cmp(&b.seq))
+    });
+
+    let results: Vec<SearchHit> = ranked.into_iter().map(|entry| entry.hit).collect();
+    serde_wasm_bindgen::to_value(&results)
+        .map_err(|e| JsValue::from_str(&format!("Serialization error: {:?}", e)))
+}
+
+/// A serializable index snapshot, for persisting and reloading (e.g. app
+/// restart, switching devices).
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct IndexSnapshot {
+    chunks: Vec<IndexedChunk>,
+}
+
+/// Exports the current index for the caller to persist.
+#[wasm_bindgen]
+pub fn export_semantic_index() -> Result<JsValue, JsValue> {
+    let snapshot = INDEX.with(|index| IndexSnapshot {
+        chunks: index.borrow().clone(),
+    });
+    serde_wasm_bindgen::to_value(&snapshot)
+        .map_err(|e| JsValue::from_str(&format!("Serialization error: {:?}", e)))
+}
+
+/// Reloads the index from a previously exported snapshot.
+#[wasm_bindgen]
+pub fn import_semantic_index(data: JsValue) -> Result<(), JsValue> {
+    let snapshot: IndexSnapshot = serde_wasm_bindgen::from_value(data)
+        .map_err(|e| JsValue::from_str(&format!("Deserialization error: {:?}", e)))?;
+
+    let next_seq = snapshot
+        .chunks
+        .iter()
+        .map(|c| c.seq)
+        .max()
+        .map(|m| m + 1)
+        .unwrap_or(0);
+
+    INDEX.with(|index| *index.borrow_mut() = snapshot.chunks);
+    NEXT_SEQ.with(|s| *s.borrow_mut() = next_seq);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_into_chunks_respects_paragraph_boundaries() {
+        let markdown = "# Title\n\nFirst paragraph.\n\nSecond paragraph.";
+        let chunks = split_into_chunks("msg-1", markdown);
+        assert!(!chunks.is_empty());
+        assert!(chunks.iter().all(|c| c.message_id == "msg-1"));
+        // Three short blocks merge into one chunk that starts at the heading
+        // and ends at the end of the last paragraph.
+        assert_eq!(chunks.len(), 1);
+        assert_eq!(chunks[0].start_byte, 0);
+        assert_eq!(chunks[0].end_byte, markdown.len());
+    }
+
+    #[test]
+    fn test_normalize_then_dot_equals_cosine() {
+        let a = normalize(&[3.0, 4.0]);
+        let b = normalize(&[3.0, 4.0]);
+        assert!((dot(&a, &b) - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_tied_scores_keep_earliest_inserted_on_eviction() {
+        let mut heap: BinaryHeap<HeapEntry> = BinaryHeap::with_capacity(3);
+        let top_k = 2;
+
+        for seq in 0..3 {
+            heap.push(HeapEntry {
+                score: 1.0,
+                seq,
+                hit: SearchHit {
+                    message_id: "msg".to_string(),
+                    start_byte: 0,
+                    end_byte: 0,
+                    text: String::new(),
+                    score: 1.0,
+                },
+            });
+            if heap.len() > top_k {
+                heap.pop();
+            }
+        }
+
+        let mut surviving: Vec<usize> = heap.into_vec().into_iter().map(|e| e.seq).collect();
+        surviving.sort();
+        assert_eq!(surviving, vec![0, 1]);
+    }
+}